@@ -0,0 +1,94 @@
+use core::convert::{TryFrom, TryInto};
+
+use super::{json_response_types, FetchError};
+
+/// Fee statistics for the last few ledgers, with every field parsed to a number
+///
+/// `fee_charged` and `max_fee` are absent when Horizon has not yet observed
+/// enough ledger history to compute percentiles (e.g. a freshly started
+/// network), in which case callers should fall back to `last_ledger_base_fee`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeStats {
+    pub last_ledger: u64,
+    pub last_ledger_base_fee: u32,
+    pub fee_charged: Option<FeeStatsPercentiles>,
+    pub max_fee: Option<FeeStatsPercentiles>,
+}
+
+/// The percentile buckets reported for both `fee_charged` and `max_fee`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeStatsPercentiles {
+    pub min: u32,
+    pub max: u32,
+    pub mode: u32,
+    pub p10: u32,
+    pub p20: u32,
+    pub p30: u32,
+    pub p40: u32,
+    pub p50: u32,
+    pub p60: u32,
+    pub p70: u32,
+    pub p80: u32,
+    pub p90: u32,
+    pub p95: u32,
+    pub p99: u32,
+}
+
+impl TryFrom<json_response_types::FeeStatsPercentiles> for FeeStatsPercentiles {
+    type Error = FetchError;
+
+    fn try_from(percentiles: json_response_types::FeeStatsPercentiles) -> Result<Self, Self::Error> {
+        Ok(FeeStatsPercentiles {
+            min: percentiles.min.parse()?,
+            max: percentiles.max.parse()?,
+            mode: percentiles.mode.parse()?,
+            p10: percentiles.p10.parse()?,
+            p20: percentiles.p20.parse()?,
+            p30: percentiles.p30.parse()?,
+            p40: percentiles.p40.parse()?,
+            p50: percentiles.p50.parse()?,
+            p60: percentiles.p60.parse()?,
+            p70: percentiles.p70.parse()?,
+            p80: percentiles.p80.parse()?,
+            p90: percentiles.p90.parse()?,
+            p95: percentiles.p95.parse()?,
+            p99: percentiles.p99.parse()?,
+        })
+    }
+}
+
+impl FeeStatsPercentiles {
+    /// Look up one of the percentile buckets Horizon reports (`10`, `20`, ... `90`, `95`, `99`)
+    ///
+    /// Returns `None` for any percentile Horizon doesn't bucket, so callers
+    /// can fall back to a different fee source instead of guessing.
+    pub fn percentile(&self, p: u8) -> Option<u32> {
+        match p {
+            10 => Some(self.p10),
+            20 => Some(self.p20),
+            30 => Some(self.p30),
+            40 => Some(self.p40),
+            50 => Some(self.p50),
+            60 => Some(self.p60),
+            70 => Some(self.p70),
+            80 => Some(self.p80),
+            90 => Some(self.p90),
+            95 => Some(self.p95),
+            99 => Some(self.p99),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<json_response_types::FeeStats> for FeeStats {
+    type Error = FetchError;
+
+    fn try_from(fee_stats: json_response_types::FeeStats) -> Result<Self, Self::Error> {
+        Ok(FeeStats {
+            last_ledger: fee_stats.last_ledger.parse()?,
+            last_ledger_base_fee: fee_stats.last_ledger_base_fee.parse()?,
+            fee_charged: fee_stats.fee_charged.map(TryInto::try_into).transpose()?,
+            max_fee: fee_stats.max_fee.map(TryInto::try_into).transpose()?,
+        })
+    }
+}
@@ -1,22 +1,65 @@
 use core::num::{ParseFloatError, ParseIntError};
-use sp_io::offchain::timestamp;
+use sp_io::offchain::{sleep_until, timestamp};
 use sp_runtime::offchain::{
     http::Request,
-    http::{Error, Method},
+    http::{Error, Method, Response},
     Duration, HttpError,
 };
-use sp_std::{str, vec, vec::Vec};
-use thiserror::Error; 
+use serde::de::DeserializeOwned;
+use sp_std::{cmp::min, str, vec, vec::Vec};
+use thiserror::Error;
 
 use core::convert::TryInto;
 
-use crate::{AccountId, IntoAccountId, StellarSdkError};
+use crate::{AccountId, IntoAccountId, IntoClaimbleBalanceId, StellarSdkError, XdrCodec};
 
 use super::{
-    api_response_types::FeeStats, json_response_types, Horizon, HTTP_HEADER_CLIENT_NAME,
-    HTTP_HEADER_CLIENT_VERSION,
+    api_response_types::FeeStats,
+    json_response_types::{self, PagingToken},
+    Horizon, HTTP_HEADER_CLIENT_NAME, HTTP_HEADER_CLIENT_VERSION,
 };
 
+/// Sort order for a paginated Horizon collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+impl Order {
+    fn as_query_str(self) -> &'static str {
+        match self {
+            Order::Asc => "asc",
+            Order::Desc => "desc",
+        }
+    }
+}
+
+/// The maximum `limit` Horizon accepts on a paginated collection endpoint
+const MAX_PAGE_LIMIT: u8 = 200;
+
+/// Which bucket of `fetch_fee_stats` to use when picking a base fee
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeStrategy {
+    /// The lowest fee that was recently accepted
+    Min,
+    /// The most commonly charged fee
+    Mode,
+    /// One of the percentile buckets Horizon reports (`10`, `20`, ... `95`, `99`)
+    Percentile(u8),
+    /// The highest fee that was recently accepted
+    Max,
+}
+
+/// Total fee for a transaction with `operation_count` operations at `base_fee` per operation
+///
+/// Stellar charges `base_fee` once per operation in the transaction, so the
+/// total is their product, saturating rather than overflowing on
+/// pathologically large inputs.
+pub fn total_fee_for_operation_count(base_fee: u32, operation_count: u32) -> u32 {
+    base_fee.saturating_mul(operation_count)
+}
+
 impl From<ParseIntError> for FetchError {
     fn from(error: ParseIntError) -> Self {
         FetchError::ParseIntError(error)
@@ -51,6 +94,52 @@ pub enum FetchError {
     ParseFloatError(ParseFloatError),
     #[error("Account required memo {0:?}")]
     AccountRequiredMemo(AccountId),
+    #[error("Horizon did not report fee percentiles")]
+    FeeStatsUnavailable,
+    #[error("Rate limited by Horizon, retry after {retry_after_ms:?}ms")]
+    RateLimited {
+        retry_after_ms: Option<u64>,
+        limit: Option<u32>,
+        remaining: Option<u32>,
+    },
+}
+
+/// Parse Horizon's rate-limit headers off a `429` response into a [`FetchError::RateLimited`]
+fn rate_limited_error(response: &Response) -> FetchError {
+    let headers = response.headers();
+
+    parse_rate_limit_headers(
+        headers.find("Retry-After"),
+        headers.find("X-Ratelimit-Reset"),
+        headers.find("X-Ratelimit-Limit"),
+        headers.find("X-Ratelimit-Remaining"),
+    )
+}
+
+/// Build a [`FetchError::RateLimited`] from a `429` response's header values
+///
+/// `retry_after` takes priority over `ratelimit_reset` when both are present,
+/// matching Horizon's own precedence. Unparseable or absent headers degrade
+/// to `None` rather than failing the whole response.
+fn parse_rate_limit_headers(
+    retry_after: Option<&str>,
+    ratelimit_reset: Option<&str>,
+    limit: Option<&str>,
+    remaining: Option<&str>,
+) -> FetchError {
+    let retry_after_ms = retry_after
+        .and_then(|value| value.parse::<u64>().ok())
+        .or_else(|| ratelimit_reset.and_then(|value| value.parse::<u64>().ok()))
+        .map(|seconds| seconds.saturating_mul(1000));
+
+    let limit = limit.and_then(|value| value.parse().ok());
+    let remaining = remaining.and_then(|value| value.parse().ok());
+
+    FetchError::RateLimited {
+        retry_after_ms,
+        limit,
+        remaining,
+    }
 }
 
 impl From<Error> for FetchError {
@@ -85,20 +174,107 @@ impl From<serde_json::Error> for FetchError {
     }
 }
 
+/// Returns `true` if a failed attempt is worth retrying
+///
+/// IO errors and deadlines are assumed to be transient, as is being rate
+/// limited, as are the Horizon status codes that signal the server is
+/// temporarily unable to serve the request (`502`/`503`/`504`
+/// gateway/availability errors). Everything else (parse failures, other
+/// `4xx` responses) is treated as a permanent failure and short-circuits
+/// immediately.
+fn is_retryable(error: &FetchError) -> bool {
+    matches!(
+        error,
+        FetchError::IoError
+            | FetchError::DeadlineReached
+            | FetchError::RateLimited { .. }
+            | FetchError::UnexpectedResponseStatus { status: 502, .. }
+            | FetchError::UnexpectedResponseStatus { status: 503, .. }
+            | FetchError::UnexpectedResponseStatus { status: 504, .. }
+    )
+}
+
+/// Compute the exponential backoff delay for retry number `attempt` (0-based), in milliseconds
+///
+/// Doubles `base_delay_ms` per attempt, clamped to `max_delay_ms`, plus up to
+/// `base_delay_ms` of jitter (derived by callers from the current timestamp)
+/// to avoid every retrying client waking up at once. `attempt` is shifted
+/// with `checked_shl` so a large `max_retries` can't overflow the multiplier.
+fn backoff_delay_ms(attempt: u32, base_delay_ms: u64, max_delay_ms: u64, jitter_source_ms: u64) -> u64 {
+    let base_delay = base_delay_ms.max(1);
+    let multiplier = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+    let backoff = base_delay.saturating_mul(multiplier).min(max_delay_ms);
+    let jitter = jitter_source_ms % base_delay;
+    backoff.saturating_add(jitter)
+}
+
 impl Horizon {
     pub fn request(
         &self,
         path: Vec<&[u8]>,
         method: Method,
         timeout_milliseconds: u64,
+    ) -> Result<Vec<u8>, FetchError> {
+        let mut attempt = 0;
+        loop {
+            match self.request_once(path.clone(), method.clone(), timeout_milliseconds) {
+                Ok(body) => return Ok(body),
+                Err(error) if attempt < self.retry_policy.max_retries && is_retryable(&error) => {
+                    let delay = match &error {
+                        FetchError::RateLimited {
+                            retry_after_ms: Some(retry_after_ms),
+                            ..
+                        } => (*retry_after_ms).min(self.retry_policy.max_delay_ms),
+                        _ => backoff_delay_ms(
+                            attempt,
+                            self.retry_policy.base_delay_ms,
+                            self.retry_policy.max_delay_ms,
+                            timestamp().unix_millis(),
+                        ),
+                    };
+
+                    sleep_until(timestamp().add(Duration::from_millis(delay)));
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Perform a single HTTP attempt against `base_url` joined with `path`, with no retries
+    fn request_once(
+        &self,
+        path: Vec<&[u8]>,
+        method: Method,
+        timeout_milliseconds: u64,
+    ) -> Result<Vec<u8>, FetchError> {
+        self.send(path, method, None, timeout_milliseconds)
+    }
+
+    /// Build the request, dispatch it, and interpret the response
+    ///
+    /// Shared by [`Horizon::request_once`] and [`Horizon::request_with_body`]
+    /// so the `429`/rate-limit and status handling can't drift apart between
+    /// the two call sites.
+    fn send(
+        &self,
+        path: Vec<&[u8]>,
+        method: Method,
+        body: Option<Vec<u8>>,
+        timeout_milliseconds: u64,
     ) -> Result<Vec<u8>, FetchError> {
         let mut url = self.base_url.clone();
         for path_segment in path {
             url.extend_from_slice(path_segment);
         }
 
-        let request =
-            Request::<Vec<&'static [u8]>>::new(str::from_utf8(&url).unwrap()).method(method);
+        let mut request = Request::<Vec<Vec<u8>>>::new(str::from_utf8(&url).unwrap()).method(method);
+        if let Some(body) = body {
+            request = request
+                .body(vec![body])
+                .add_header("Content-Type", "application/x-www-form-urlencoded");
+        }
+
         let timeout = timestamp().add(Duration::from_millis(timeout_milliseconds));
         let pending = request
             .add_header("X-Client-Name", HTTP_HEADER_CLIENT_NAME)
@@ -111,6 +287,10 @@ impl Horizon {
             .map_err(|_| FetchError::DeadlineReached)?;
         let response = response?;
 
+        if response.code == 429 {
+            return Err(rate_limited_error(&response));
+        }
+
         if response.code != 200 {
             return Err(FetchError::UnexpectedResponseStatus {
                 status: response.code,
@@ -132,6 +312,31 @@ impl Horizon {
         response.try_into()
     }
 
+    /// Suggest a base fee per operation, priced off the current `fee_charged` percentiles
+    ///
+    /// Falls back to `last_ledger_base_fee` if `strategy` has no matching
+    /// percentile, or errors with `FeeStatsUnavailable` if Horizon reports none.
+    pub fn suggested_base_fee(
+        &self,
+        strategy: FeeStrategy,
+        timeout_milliseconds: u64,
+    ) -> Result<u32, FetchError> {
+        let fee_stats = self.fetch_fee_stats(timeout_milliseconds)?;
+
+        let percentiles = fee_stats
+            .fee_charged
+            .ok_or(FetchError::FeeStatsUnavailable)?;
+
+        let fee = match strategy {
+            FeeStrategy::Min => Some(percentiles.min),
+            FeeStrategy::Mode => Some(percentiles.mode),
+            FeeStrategy::Percentile(p) => percentiles.percentile(p),
+            FeeStrategy::Max => Some(percentiles.max),
+        };
+
+        Ok(fee.unwrap_or(fee_stats.last_ledger_base_fee))
+    }
+
     /// Fetch the sequence number of an account
     ///
     /// The sequence number is defined to be of type [i64](https://github.com/stellar/stellar-core/blob/master/src/xdr/Stellar-ledger-entries.x)
@@ -168,4 +373,237 @@ impl Horizon {
         let next_sequence_number = sequence_number + 1;
         Ok(next_sequence_number)
     }
+
+    /// Fetch one page of a paginated Horizon collection endpoint
+    ///
+    /// `path` may already carry its own filter query string (e.g.
+    /// `b"/claimable_balances?claimant=G..."`). `limit` is clamped to
+    /// Horizon's maximum of `200` records per page.
+    pub fn fetch_page<T: DeserializeOwned + PagingToken>(
+        &self,
+        path: &[u8],
+        cursor: Option<&[u8]>,
+        limit: u8,
+        order: Order,
+        timeout_milliseconds: u64,
+    ) -> Result<json_response_types::Page<T>, FetchError> {
+        let mut query = Vec::from(path);
+        query.extend_from_slice(if path.contains(&b'?') { b"&" } else { b"?" });
+        query.extend_from_slice(b"limit=");
+        query.extend_from_slice(min(limit, MAX_PAGE_LIMIT).to_string().as_bytes());
+        query.extend_from_slice(b"&order=");
+        query.extend_from_slice(order.as_query_str().as_bytes());
+        if let Some(cursor) = cursor {
+            query.extend_from_slice(b"&cursor=");
+            query.extend_from_slice(cursor);
+        }
+
+        let json = self.request(vec![query.as_slice()], Method::Get, timeout_milliseconds)?;
+
+        let page: json_response_types::Page<T> = serde_json::from_slice(&json)?;
+        Ok(page)
+    }
+
+    /// Walk a paginated Horizon collection endpoint to completion
+    ///
+    /// Follows the `paging_token` of each page's last record until Horizon
+    /// returns an empty page or `record_budget` records have been collected.
+    pub fn fetch_all_pages<T: DeserializeOwned + PagingToken>(
+        &self,
+        path: &[u8],
+        order: Order,
+        record_budget: usize,
+        timeout_milliseconds: u64,
+    ) -> Result<Vec<T>, FetchError> {
+        let mut records = Vec::new();
+        let mut cursor: Option<Vec<u8>> = None;
+
+        while records.len() < record_budget {
+            let limit = min(MAX_PAGE_LIMIT as usize, record_budget - records.len()) as u8;
+            let page = self.fetch_page::<T>(
+                path,
+                cursor.as_deref(),
+                limit,
+                order,
+                timeout_milliseconds,
+            )?;
+
+            let mut page_records = page.into_records();
+            if page_records.is_empty() {
+                break;
+            }
+
+            cursor = page_records
+                .last()
+                .map(|record| record.paging_token().as_bytes().to_vec());
+            records.append(&mut page_records);
+        }
+
+        Ok(records)
+    }
+
+    /// Fetch a claimable balance by id
+    pub fn fetch_claimable_balance<T: IntoClaimbleBalanceId>(
+        &self,
+        id: T,
+        timeout_milliseconds: u64,
+    ) -> Result<json_response_types::ClaimableBalanceResponse, StellarSdkError> {
+        let balance_id = id.into_claimable_balance_id()?;
+        let hex_id = hex::encode(balance_id.to_xdr());
+
+        self.fetch_claimable_balance_by_hex(hex_id.as_bytes(), timeout_milliseconds)
+            .map_err(StellarSdkError::from)
+    }
+
+    fn fetch_claimable_balance_by_hex(
+        &self,
+        hex_id: &[u8],
+        timeout_milliseconds: u64,
+    ) -> Result<json_response_types::ClaimableBalanceResponse, FetchError> {
+        let json = self.request(
+            vec![b"/claimable_balances/", hex_id],
+            Method::Get,
+            timeout_milliseconds,
+        )?;
+
+        let response: json_response_types::ClaimableBalanceResponse = serde_json::from_slice(&json)?;
+        Ok(response)
+    }
+
+    /// Fetch the claimable balances that `claimant` may claim
+    pub fn fetch_claimable_balances_by_claimant<T: IntoAccountId>(
+        &self,
+        claimant: T,
+        order: Order,
+        record_budget: usize,
+        timeout_milliseconds: u64,
+    ) -> Result<Vec<json_response_types::ClaimableBalanceResponse>, FetchError> {
+        let mut path = Vec::from(&b"/claimable_balances?claimant="[..]);
+        path.extend_from_slice(claimant.into_encoding().as_slice());
+
+        self.fetch_all_pages(&path, order, record_budget, timeout_milliseconds)
+    }
+
+    /// Fetch the claimable balances sponsored by `sponsor`
+    pub fn fetch_claimable_balances_by_sponsor<T: IntoAccountId>(
+        &self,
+        sponsor: T,
+        order: Order,
+        record_budget: usize,
+        timeout_milliseconds: u64,
+    ) -> Result<Vec<json_response_types::ClaimableBalanceResponse>, FetchError> {
+        let mut path = Vec::from(&b"/claimable_balances?sponsor="[..]);
+        path.extend_from_slice(sponsor.into_encoding().as_slice());
+
+        self.fetch_all_pages(&path, order, record_budget, timeout_milliseconds)
+    }
+
+    /// Fetch a transaction by its hex-encoded hash
+    pub fn fetch_transaction(
+        &self,
+        hash_hex: &[u8],
+        timeout_milliseconds: u64,
+    ) -> Result<json_response_types::TransactionResponse, FetchError> {
+        let json = self.request(
+            vec![b"/transactions/", hash_hex],
+            Method::Get,
+            timeout_milliseconds,
+        )?;
+
+        let response: json_response_types::TransactionResponse = serde_json::from_slice(&json)?;
+        Ok(response)
+    }
+
+    /// Perform a single `POST` with a form-encoded body, bypassing the retry layer
+    ///
+    /// Used for submitting transactions: blindly retrying a `POST
+    /// /transactions` is not safe (the transaction may already have been
+    /// applied), so submission failures are instead handled by
+    /// [`Horizon::submit_and_confirm`] polling for the outcome.
+    pub(crate) fn request_with_body(
+        &self,
+        path: Vec<&[u8]>,
+        method: Method,
+        body: Vec<u8>,
+        timeout_milliseconds: u64,
+    ) -> Result<Vec<u8>, FetchError> {
+        self.send(path, method, Some(body), timeout_milliseconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_delay_doubles_each_attempt_before_the_cap() {
+        assert_eq!(backoff_delay_ms(0, 100, 10_000, 0), 100);
+        assert_eq!(backoff_delay_ms(1, 100, 10_000, 0), 200);
+        assert_eq!(backoff_delay_ms(2, 100, 10_000, 0), 400);
+    }
+
+    #[test]
+    fn backoff_delay_saturates_at_max_delay_ms() {
+        assert_eq!(backoff_delay_ms(10, 100, 500, 0), 500);
+    }
+
+    #[test]
+    fn backoff_delay_does_not_overflow_at_high_attempt_counts() {
+        assert_eq!(backoff_delay_ms(1000, 100, 5_000, 0), 5_000);
+        assert_eq!(backoff_delay_ms(u32::MAX, 100, 5_000, 0), 5_000);
+    }
+
+    #[test]
+    fn backoff_delay_adds_jitter_bounded_by_base_delay() {
+        assert_eq!(backoff_delay_ms(0, 100, 10_000, 250), 100 + 250 % 100);
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_into_millis() {
+        let error = parse_rate_limit_headers(Some("2"), None, Some("10"), Some("3"));
+        assert_eq!(
+            error,
+            FetchError::RateLimited {
+                retry_after_ms: Some(2_000),
+                limit: Some(10),
+                remaining: Some(3),
+            }
+        );
+    }
+
+    #[test]
+    fn falls_back_to_ratelimit_reset_when_retry_after_is_absent() {
+        let error = parse_rate_limit_headers(None, Some("5"), None, None);
+        assert_eq!(
+            error,
+            FetchError::RateLimited {
+                retry_after_ms: Some(5_000),
+                limit: None,
+                remaining: None,
+            }
+        );
+    }
+
+    #[test]
+    fn prefers_retry_after_over_ratelimit_reset_when_both_are_present() {
+        let error = parse_rate_limit_headers(Some("1"), Some("99"), None, None);
+        assert_eq!(error, FetchError::RateLimited {
+            retry_after_ms: Some(1_000),
+            limit: None,
+            remaining: None,
+        });
+    }
+
+    #[test]
+    fn unparseable_headers_degrade_to_none_instead_of_failing() {
+        let error = parse_rate_limit_headers(Some("soon"), None, Some("many"), None);
+        assert_eq!(
+            error,
+            FetchError::RateLimited {
+                retry_after_ms: None,
+                limit: None,
+                remaining: None,
+            }
+        );
+    }
 }
@@ -0,0 +1,161 @@
+use serde::Deserialize;
+use sp_std::{string::String, vec::Vec};
+
+/// The raw `GET /accounts/{id}` response, before any field parsing
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountResponse {
+    pub sequence: String,
+}
+
+/// The raw `GET /fee_stats` response, with every numeric field still a string
+///
+/// Horizon serializes fee statistics as strings, so this is parsed into
+/// [`super::api_response_types::FeeStats`] before being handed to callers.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeeStats {
+    pub last_ledger: String,
+    pub last_ledger_base_fee: String,
+    pub ledger_capacity_usage: String,
+    #[serde(default)]
+    pub fee_charged: Option<FeeStatsPercentiles>,
+    #[serde(default)]
+    pub max_fee: Option<FeeStatsPercentiles>,
+}
+
+/// The percentile buckets Horizon reports for both `fee_charged` and `max_fee`
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeeStatsPercentiles {
+    pub min: String,
+    pub max: String,
+    pub mode: String,
+    pub p10: String,
+    pub p20: String,
+    pub p30: String,
+    pub p40: String,
+    pub p50: String,
+    pub p60: String,
+    pub p70: String,
+    pub p80: String,
+    pub p90: String,
+    pub p95: String,
+    pub p99: String,
+}
+
+/// A Horizon `Link` object, as found in a resource's `_links` section
+#[derive(Debug, Clone, Deserialize)]
+pub struct Link {
+    pub href: sp_std::string::String,
+}
+
+/// The `_links` section of a paginated collection response
+#[derive(Debug, Clone, Deserialize)]
+pub struct PageLinks {
+    pub next: Option<Link>,
+    pub prev: Option<Link>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Embedded<T> {
+    records: Vec<T>,
+}
+
+/// Implemented by records returned from a paginated Horizon collection
+///
+/// Horizon cursors are opaque strings taken from the `paging_token` field of
+/// a record, so walking a collection only requires being able to read that
+/// field back off of the last record of a page.
+pub trait PagingToken {
+    fn paging_token(&self) -> &str;
+}
+
+/// A single page of a Horizon paginated collection
+///
+/// Deserializes the `_embedded.records` array together with the `_links`
+/// section, so callers can inspect a page directly; to walk the whole
+/// collection, use [`super::Horizon::fetch_all_pages`] instead.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Page<T> {
+    #[serde(rename = "_embedded")]
+    embedded: Embedded<T>,
+    #[serde(rename = "_links")]
+    links: PageLinks,
+}
+
+impl<T> Page<T> {
+    pub fn records(&self) -> &[T] {
+        &self.embedded.records
+    }
+
+    pub fn into_records(self) -> Vec<T> {
+        self.embedded.records
+    }
+
+    pub fn links(&self) -> &PageLinks {
+        &self.links
+    }
+}
+
+impl<T: PagingToken> Page<T> {
+    /// The `paging_token` of the last record on this page, if any
+    pub fn last_paging_token(&self) -> Option<&str> {
+        self.embedded.records.last().map(PagingToken::paging_token)
+    }
+}
+
+/// A single condition of a [`Claimant`]'s claim predicate
+///
+/// Mirrors Horizon's JSON representation of `ClaimPredicate`, which nests
+/// logical combinators (`and`/`or`/`not`) around the leaf conditions
+/// (`unconditional`, `abs_before`, `rel_before`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimPredicate {
+    Unconditional(bool),
+    And(Vec<ClaimPredicate>),
+    Or(Vec<ClaimPredicate>),
+    Not(sp_std::boxed::Box<ClaimPredicate>),
+    AbsBefore(String),
+    RelBefore(String),
+}
+
+/// One claimant of a claimable balance, together with the predicate gating their claim
+#[derive(Debug, Clone, Deserialize)]
+pub struct Claimant {
+    pub destination: String,
+    pub predicate: ClaimPredicate,
+}
+
+/// The response returned by `GET /claimable_balances/{id}` and the
+/// `/claimable_balances` collection endpoints
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClaimableBalanceResponse {
+    pub id: String,
+    pub asset: String,
+    pub amount: String,
+    pub sponsor: Option<String>,
+    pub claimants: Vec<Claimant>,
+    pub paging_token: String,
+}
+
+impl PagingToken for ClaimableBalanceResponse {
+    fn paging_token(&self) -> &str {
+        &self.paging_token
+    }
+}
+
+/// The response returned by `POST /transactions` for a transaction Horizon applied
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubmitTransactionResponse {
+    pub hash: String,
+    pub ledger: u32,
+    pub result_xdr: String,
+}
+
+/// The response returned by `GET /transactions/{hash}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransactionResponse {
+    pub hash: String,
+    pub ledger: u32,
+    pub successful: bool,
+    pub result_xdr: String,
+}
@@ -11,9 +11,37 @@ pub mod submit_transaction;
 pub struct Horizon {
     base_url: Vec<u8>,
     agent: Agent,
+    retry_policy: RetryPolicy,
 }
 
-pub use fetch::FetchError;
+pub use fetch::{FeeStrategy, FetchError};
+
+/// Controls how [`Horizon::request`] retries transient failures
+///
+/// A retry is only attempted for failures that are likely to succeed on a
+/// later attempt (IO errors, deadlines, and Horizon's `429`/`502`/`503`/`504`
+/// responses). Parse failures and other `4xx` responses are returned
+/// immediately regardless of this policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries attempted after the initial request before giving up
+    pub max_retries: u32,
+    /// Base delay used for the exponential backoff, in milliseconds
+    pub base_delay_ms: u64,
+    /// Upper bound on the computed backoff delay, in milliseconds
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    /// No retries by default, preserving the previous single-attempt behaviour
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            base_delay_ms: 200,
+            max_delay_ms: 5_000,
+        }
+    }
+}
 
 impl Horizon {
     pub fn new(base_url: &str) -> Horizon {
@@ -25,8 +53,15 @@ impl Horizon {
         Horizon {
             base_url: base_url.as_bytes().to_vec(),
             agent,
+            retry_policy: RetryPolicy::default(),
         }
     }
+
+    /// Replace the default [`RetryPolicy`] used by every `fetch_*` call on this client
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Horizon {
+        self.retry_policy = retry_policy;
+        self
+    }
 }
 
 const HTTP_HEADER_CLIENT_NAME: &str = "substrate-stellar-sdk";
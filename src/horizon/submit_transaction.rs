@@ -0,0 +1,218 @@
+use sp_io::offchain::{sleep_until, timestamp};
+use sp_runtime::offchain::{http::Method, Duration};
+use sp_std::{vec, vec::Vec};
+
+use crate::{FeeBumpTransaction, IntoMuxedAccountId, StellarSdkError, TransactionEnvelope};
+
+use super::{
+    fetch::{total_fee_for_operation_count, FeeStrategy, FetchError},
+    json_response_types, Horizon,
+};
+
+#[derive(serde::Deserialize)]
+struct SubmissionResultCodes {
+    transaction: sp_std::string::String,
+}
+
+#[derive(serde::Deserialize)]
+struct SubmissionErrorExtras {
+    extras: SubmissionExtras,
+}
+
+#[derive(serde::Deserialize)]
+struct SubmissionExtras {
+    result_codes: SubmissionResultCodes,
+}
+
+/// `true` if Horizon's `400` body carries a result code meaning the outcome is still unknown
+fn is_ambiguous_result_code(body: &[u8]) -> bool {
+    match serde_json::from_slice::<SubmissionErrorExtras>(body) {
+        Ok(error) => matches!(
+            error.extras.result_codes.transaction.as_str(),
+            "tx_too_late" | "timeout"
+        ),
+        Err(_) => false,
+    }
+}
+
+/// `true` if a submission failure doesn't rule out the transaction having been applied anyway
+fn is_ambiguous_submission_failure(error: &FetchError) -> bool {
+    match error {
+        FetchError::DeadlineReached => true,
+        FetchError::UnexpectedResponseStatus { status: 504, .. } => true,
+        FetchError::UnexpectedResponseStatus { status: 400, body } => is_ambiguous_result_code(body),
+        _ => false,
+    }
+}
+
+/// The outcome of waiting for a submitted transaction to be applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmissionOutcome {
+    /// Whether the transaction was found included in a ledger
+    pub included: bool,
+    /// The `TransactionResult` XDR Horizon reported, decoded from base64, if included
+    pub result_xdr: Option<Vec<u8>>,
+    /// The ledger sequence the transaction was included in, if included
+    pub ledger: Option<u32>,
+}
+
+impl Horizon {
+    /// Submit a signed transaction envelope to Horizon
+    pub fn submit_transaction(
+        &self,
+        envelope: &TransactionEnvelope,
+        timeout_milliseconds: u64,
+    ) -> Result<json_response_types::SubmitTransactionResponse, FetchError> {
+        let mut body = Vec::from(&b"tx="[..]);
+        body.extend_from_slice(envelope.to_base64_xdr().as_slice());
+
+        let json =
+            self.request_with_body(vec![b"/transactions"], Method::Post, body, timeout_milliseconds)?;
+
+        let response: json_response_types::SubmitTransactionResponse = serde_json::from_slice(&json)?;
+        Ok(response)
+    }
+
+    /// Submit `envelope`, then poll Horizon until it is found in a ledger or the attempt budget runs out
+    ///
+    /// A submission timeout (`504`/deadline, or a `400` with a `timeout`/
+    /// `tx_too_late` result code) doesn't rule out the network having
+    /// applied the transaction anyway, so those outcomes fall back to
+    /// polling `GET /transactions/{hash}` instead of returning immediately.
+    pub fn submit_and_confirm(
+        &self,
+        envelope: &TransactionEnvelope,
+        confirm_attempts: u32,
+        poll_interval_ms: u64,
+        timeout_milliseconds: u64,
+    ) -> Result<SubmissionOutcome, FetchError> {
+        match self.submit_transaction(envelope, timeout_milliseconds) {
+            Ok(response) => {
+                return Ok(SubmissionOutcome {
+                    included: true,
+                    result_xdr: base64::decode(response.result_xdr).ok(),
+                    ledger: Some(response.ledger),
+                })
+            }
+            Err(error) if is_ambiguous_submission_failure(&error) => {}
+            Err(error) => return Err(error),
+        }
+
+        let hash_hex = hex::encode(envelope.hash());
+
+        for _ in 0..confirm_attempts {
+            sleep_until(timestamp().add(Duration::from_millis(poll_interval_ms)));
+
+            match self.fetch_transaction(hash_hex.as_bytes(), timeout_milliseconds) {
+                Ok(response) => {
+                    return Ok(SubmissionOutcome {
+                        included: true,
+                        result_xdr: base64::decode(response.result_xdr).ok(),
+                        ledger: Some(response.ledger),
+                    })
+                }
+                Err(FetchError::UnexpectedResponseStatus { status: 404, .. }) => continue,
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(SubmissionOutcome {
+            included: false,
+            result_xdr: None,
+            ledger: None,
+        })
+    }
+
+    /// Wrap `envelope` in a [`FeeBumpTransaction`] at a higher base fee and resubmit it
+    ///
+    /// Fails with [`StellarSdkError::CantWrapFeeBumpTransaction`] if
+    /// `envelope` is already a fee-bump transaction.
+    pub fn fee_bump_and_resubmit<T: IntoMuxedAccountId>(
+        &self,
+        fee_source: T,
+        envelope: TransactionEnvelope,
+        strategy: FeeStrategy,
+        confirm_attempts: u32,
+        poll_interval_ms: u64,
+        timeout_milliseconds: u64,
+    ) -> Result<SubmissionOutcome, StellarSdkError> {
+        let fee_source = fee_source.into_muxed_account_id()?;
+        let operation_count = envelope.operation_count();
+        let base_fee = self.suggested_base_fee(strategy, timeout_milliseconds)?;
+        let fee = total_fee_for_operation_count(base_fee, operation_count);
+
+        let fee_bump_envelope =
+            FeeBumpTransaction::new(fee_source, fee as i64, envelope)?.into_transaction_envelope();
+
+        Ok(self.submit_and_confirm(
+            &fee_bump_envelope,
+            confirm_attempts,
+            poll_interval_ms,
+            timeout_milliseconds,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tx_too_late_result_code_is_ambiguous() {
+        let body = br#"{"extras":{"result_codes":{"transaction":"tx_too_late"}}}"#;
+        assert!(is_ambiguous_result_code(body));
+    }
+
+    #[test]
+    fn timeout_result_code_is_ambiguous() {
+        let body = br#"{"extras":{"result_codes":{"transaction":"timeout"}}}"#;
+        assert!(is_ambiguous_result_code(body));
+    }
+
+    #[test]
+    fn other_result_codes_are_not_ambiguous() {
+        let body = br#"{"extras":{"result_codes":{"transaction":"tx_bad_seq"}}}"#;
+        assert!(!is_ambiguous_result_code(body));
+    }
+
+    #[test]
+    fn unparseable_body_is_not_ambiguous() {
+        assert!(!is_ambiguous_result_code(b"not json"));
+    }
+
+    #[test]
+    fn deadline_and_504_failures_are_ambiguous() {
+        assert!(is_ambiguous_submission_failure(&FetchError::DeadlineReached));
+        assert!(is_ambiguous_submission_failure(
+            &FetchError::UnexpectedResponseStatus {
+                status: 504,
+                body: Vec::new(),
+            }
+        ));
+    }
+
+    #[test]
+    fn a_400_is_only_ambiguous_with_a_matching_result_code() {
+        let ambiguous = FetchError::UnexpectedResponseStatus {
+            status: 400,
+            body: br#"{"extras":{"result_codes":{"transaction":"timeout"}}}"#.to_vec(),
+        };
+        assert!(is_ambiguous_submission_failure(&ambiguous));
+
+        let not_ambiguous = FetchError::UnexpectedResponseStatus {
+            status: 400,
+            body: br#"{"extras":{"result_codes":{"transaction":"tx_bad_seq"}}}"#.to_vec(),
+        };
+        assert!(!is_ambiguous_submission_failure(&not_ambiguous));
+    }
+
+    #[test]
+    fn other_statuses_are_not_ambiguous() {
+        assert!(!is_ambiguous_submission_failure(
+            &FetchError::UnexpectedResponseStatus {
+                status: 500,
+                body: Vec::new(),
+            }
+        ));
+    }
+}